@@ -0,0 +1,95 @@
+//! The accumulator type shared by the permutation, position and property
+//! coordinate systems.
+//!
+//! By default this is `usize`, which caps every encoder at whatever fits in a
+//! `usize` (e.g. 20! for permutations). Enabling the `bigint` feature swaps it
+//! for `num_bigint::BigUint`, removing that ceiling at the cost of an
+//! allocation per coordinate.
+
+#[cfg(not(feature = "bigint"))]
+pub type Coord = usize;
+#[cfg(feature = "bigint")]
+pub type Coord = num_bigint::BigUint;
+
+/// The additive identity, as a `Coord`.
+#[cfg(not(feature = "bigint"))]
+pub fn zero() -> Coord {
+    0
+}
+#[cfg(feature = "bigint")]
+pub fn zero() -> Coord {
+    Coord::from(0u32)
+}
+
+/// `n!` as a `Coord`.
+#[cfg(not(feature = "bigint"))]
+pub fn factorial(n: usize) -> Coord {
+    use factorial::Factorial;
+    n.factorial()
+}
+#[cfg(feature = "bigint")]
+pub fn factorial(n: usize) -> Coord {
+    (1..=n as u64).map(Coord::from).product()
+}
+
+/// `n` choose `k` as a `Coord`.
+#[cfg(not(feature = "bigint"))]
+pub fn binomial(n: usize, k: usize) -> Coord {
+    num_integer::binomial(n, k)
+}
+#[cfg(feature = "bigint")]
+pub fn binomial(n: usize, k: usize) -> Coord {
+    if k > n {
+        return Coord::from(0u32);
+    }
+    let k = k.min(n - k);
+    (0..k).fold(Coord::from(1u32), |acc, i| {
+        (acc * Coord::from((n - i) as u64)) / Coord::from((i + 1) as u64)
+    })
+}
+
+/// Render a `Coord` as its digits in the given `base`, e.g. for the per-position
+/// property digits decoded by the property decoder.
+#[cfg(not(feature = "bigint"))]
+pub fn to_radix_string(value: Coord, base: u8) -> String {
+    format!("{}", radix_fmt::radix(value, base))
+}
+#[cfg(feature = "bigint")]
+pub fn to_radix_string(value: Coord, base: u8) -> String {
+    value.to_str_radix(base as u32)
+}
+
+/// Parse a `Coord` from a non-empty string of digits in the given `base`, the
+/// inverse of `to_radix_string`. Panics if `s` is not such a string.
+#[cfg(not(feature = "bigint"))]
+pub fn from_radix_str(s: &str, base: u8) -> Coord {
+    usize::from_str_radix(s, base as u32).expect("a non-empty string of digits in the given base")
+}
+#[cfg(feature = "bigint")]
+pub fn from_radix_str(s: &str, base: u8) -> Coord {
+    Coord::parse_bytes(s.as_bytes(), base as u32)
+        .expect("a non-empty string of digits in the given base")
+}
+
+/// Draw a `Coord` uniformly from `0..upper`.
+#[cfg(all(feature = "rand", not(feature = "bigint")))]
+pub fn random_below<R: rand::Rng>(rng: &mut R, upper: Coord) -> Coord {
+    rng.gen_range(0..upper)
+}
+#[cfg(all(feature = "rand", feature = "bigint"))]
+pub fn random_below<R: rand::Rng>(rng: &mut R, upper: Coord) -> Coord {
+    use num_bigint::RandBigInt;
+    rng.gen_biguint_range(&zero(), &upper)
+}
+
+/// Narrow a `Coord` down to a `usize`, for values the caller knows are small
+/// (e.g. a single factorial-number-system digit) regardless of how wide `Coord` is.
+#[cfg(not(feature = "bigint"))]
+pub fn to_usize(value: &Coord) -> usize {
+    *value
+}
+#[cfg(feature = "bigint")]
+pub fn to_usize(value: &Coord) -> usize {
+    use num_traits::ToPrimitive;
+    value.to_usize().expect("value should fit in a usize")
+}
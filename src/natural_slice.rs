@@ -0,0 +1,73 @@
+use crate::coord::Coord;
+use crate::{permutation, position, property};
+use std::convert::TryFrom;
+
+/// Blanket extension trait bringing the permutation, position and property coordinate systems
+/// together as methods on any `T: Ord` slice, instead of three separately-imported free
+/// functions.
+pub trait NaturalSlice<T: Ord> {
+    /// See `permutation::encode_permutation`.
+    fn encode_permutation<Encoded: TryFrom<Coord>>(&self) -> Result<Encoded, Encoded::Error>;
+    /// See `position::encode_position`.
+    fn encode_position<Encoded: TryFrom<Coord>>(
+        &self,
+        is_interesting: &dyn Fn(&T) -> bool,
+    ) -> Result<Encoded, Encoded::Error>;
+    /// See `property::encode_property`.
+    fn encode_property<Encoded: TryFrom<Coord>>(
+        &self,
+        property_mapping: &dyn Fn(&T) -> u8,
+        base: u8,
+    ) -> Result<Encoded, Encoded::Error>;
+}
+
+impl<T: Ord> NaturalSlice<T> for [T] {
+    fn encode_permutation<Encoded: TryFrom<Coord>>(&self) -> Result<Encoded, Encoded::Error> {
+        permutation::encode_permutation(self)
+    }
+    fn encode_position<Encoded: TryFrom<Coord>>(
+        &self,
+        is_interesting: &dyn Fn(&T) -> bool,
+    ) -> Result<Encoded, Encoded::Error> {
+        position::encode_position(self, is_interesting)
+    }
+    fn encode_property<Encoded: TryFrom<Coord>>(
+        &self,
+        property_mapping: &dyn Fn(&T) -> u8,
+        base: u8,
+    ) -> Result<Encoded, Encoded::Error> {
+        property::encode_property(self, property_mapping, base)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    const SEQ: [u8; 8] = [3, 6, 5, 7, 0, 2, 1, 4];
+    fn is_interesting(x: &u8) -> bool {
+        *x != 0
+    }
+    fn mapping(x: &u8) -> u8 {
+        match *x {
+            3 | 4 => 2,
+            7 | 0 => 1,
+            6 | 5 | 2 | 1 => 0,
+            _ => panic!("should only be called with values from seq"),
+        }
+    }
+    #[test]
+    fn test_encode_permutation() {
+        assert_eq!(Ok(21021), SEQ.encode_permutation());
+    }
+    #[test]
+    fn test_encode_position() {
+        assert_eq!(
+            Ok(8),
+            [1u8, 0, 0, 0, 0, 0, 0, 0, 0, 1, 1, 1].encode_position(&is_interesting)
+        );
+    }
+    #[test]
+    fn test_encode_property() {
+        assert_eq!(Ok(1494), SEQ.encode_property(&mapping, 3));
+    }
+}
@@ -1,7 +1,78 @@
-use factorial::Factorial;
+use crate::coord::{self, Coord};
+use num_integer::Integer;
 use std::cmp::Ord;
 use std::convert::{TryFrom, TryInto};
 
+/// A Fenwick (binary-indexed) tree over `0..len` supporting point updates and
+/// prefix-sum queries in O(log len).
+///
+/// Used both to count "how many already-seen values are less than this one"
+/// while encoding, and to answer "what's the k-th remaining value" while
+/// decoding, so that both directions run in O(n log n) instead of O(n²).
+struct Fenwick {
+    tree: Vec<usize>,
+}
+
+impl Fenwick {
+    fn new(len: usize) -> Self {
+        Self {
+            tree: vec![0; len + 1],
+        }
+    }
+    /// Add `delta` (positive to mark, negative to clear) at 0-indexed position `i`.
+    fn add(&mut self, i: usize, delta: isize) {
+        let mut i = i + 1;
+        while i < self.tree.len() {
+            if delta < 0 {
+                self.tree[i] -= (-delta) as usize;
+            } else {
+                self.tree[i] += delta as usize;
+            }
+            i += i & i.wrapping_neg();
+        }
+    }
+    /// Sum of marks at 0-indexed positions `0..i`.
+    fn prefix_sum(&self, i: usize) -> usize {
+        let mut i = i;
+        let mut sum = 0;
+        while i > 0 {
+            sum += self.tree[i];
+            i -= i & i.wrapping_neg();
+        }
+        sum
+    }
+    /// Find the 0-indexed position of the k-th (0-indexed) still-marked slot,
+    /// then clear it. Runs in O(log len) by descending the tree bit by bit
+    /// instead of binary-searching with repeated `prefix_sum` calls.
+    fn find_and_clear_nth(&mut self, n: usize) -> usize {
+        let mut pos = 0;
+        let mut remaining = n + 1;
+        let mut step = self.tree.len().next_power_of_two() / 2;
+        while step > 0 {
+            let next = pos + step;
+            if next < self.tree.len() && self.tree[next] < remaining {
+                pos = next;
+                remaining -= self.tree[next];
+            }
+            step /= 2;
+        }
+        self.add(pos, -1);
+        pos
+    }
+}
+
+/// Map each value in `data` to its dense rank among the distinct values
+/// present, so a Fenwick tree of size `data.len()` can be indexed by rank
+/// instead of by value.
+fn compress_to_ranks<T: Ord>(data: &[T]) -> Vec<usize> {
+    let mut distinct = data.iter().collect::<Vec<_>>();
+    distinct.sort();
+    distinct.dedup();
+    data.iter()
+        .map(|x| distinct.binary_search(&x).unwrap())
+        .collect()
+}
+
 struct PermutationCounts {
     // Each count represents the number of items positioned to the left of the value at this index
     // that are lower to that value.
@@ -15,16 +86,11 @@ struct PermutationCounts {
 }
 
 impl PermutationCounts {
-    fn calculate_count<T: Ord>(pos: usize, x: &T, data: &[T]) -> usize {
-        // Count items that are positioned to the left of this value, but are lower.
-        // 0 in a descendingly sorted collection
-        data.iter().take(pos).filter(|y| y < &x).count()
-    }
-    fn encode_count(indexed_count: (usize, usize)) -> usize {
+    fn encode_count(indexed_count: (usize, usize)) -> Coord {
         let (index, count) = indexed_count;
-        count * index.factorial()
+        Coord::from(count) * coord::factorial(index)
     }
-    fn encode(&self) -> usize {
+    fn encode(&self) -> Coord {
         self.counts
             .iter()
             .cloned()
@@ -33,21 +99,27 @@ impl PermutationCounts {
             .sum()
     }
     fn from_data<T: Ord>(data: &[T]) -> Self {
-        Self {
-            counts: data
-                .iter()
-                .enumerate()
-                .map(|(index, x)| Self::calculate_count(index, x, data))
-                .collect(),
-        }
+        let ranks = compress_to_ranks(data);
+        let mut seen = Fenwick::new(data.len());
+        let counts = ranks
+            .iter()
+            .map(|&rank| {
+                // Number of already-seen values with a lower rank, i.e. already to the
+                // left and lower than the value at this position.
+                let count = seen.prefix_sum(rank);
+                seen.add(rank, 1);
+                count
+            })
+            .collect();
+        Self { counts }
     }
-    fn decode_count(index: usize, permutation: &mut usize) -> usize {
-        let factorial = index.factorial();
-        let count = permutation.div_euclid(factorial);
-        *permutation = permutation.rem_euclid(factorial);
-        count
+    fn decode_count(index: usize, permutation: &mut Coord) -> usize {
+        let factorial = coord::factorial(index);
+        let (count, remainder) = permutation.div_rem(&factorial);
+        *permutation = remainder;
+        coord::to_usize(&count)
     }
-    fn decode(mut permutation: usize, n: usize) -> Self {
+    fn decode(mut permutation: Coord, n: usize) -> Self {
         // Must be decoded in reverse order as we'll remove parts from the permutation number
         let mut counts = (0..n)
             .rev()
@@ -56,23 +128,39 @@ impl PermutationCounts {
         counts.reverse();
         Self { counts }
     }
-    fn nth_smallest<T: Ord + Clone>(n: usize, increasing: &[T], permuted: &[T]) -> T {
-        increasing
-            .iter()
-            .filter(|x| !permuted.contains(*x))
-            .nth(n)
-            .unwrap()
-            .clone()
-    }
-    fn apply<T: Ord + Clone>(&self, data: &[T]) -> Vec<T> {
-        let mut increasing = data.to_vec();
-        increasing.sort();
-        let mut permuted = Vec::new();
-        for count in self.counts.iter().rev() {
-            permuted.push(Self::nth_smallest(*count, &increasing, &permuted));
+    /// For each output position `i`, the index into a sorted slice of the same length that
+    /// holds the value which belongs at `i`.
+    fn index_permutation(&self) -> Vec<usize> {
+        let n = self.counts.len();
+        let mut available = Fenwick::new(n);
+        for i in 0..n {
+            available.add(i, 1);
+        }
+        let mut perm = vec![0; n];
+        for (i, count) in self.counts.iter().enumerate().rev() {
+            perm[i] = available.find_and_clear_nth(*count);
+        }
+        perm
+    }
+    /// Rearrange an already-sorted `xs` in place, following the permutation's cycles so each
+    /// element is swapped directly into its final position without an auxiliary buffer.
+    fn apply_mut<T: Ord>(&self, xs: &mut [T]) {
+        let perm = self.index_permutation();
+        // `perm[i]` names, for each output position `i`, which sorted index its value comes
+        // from. The swap-the-cycle trick below moves "the element currently held at position j"
+        // to position j's destination, so it wants the inverse mapping: for each sorted index,
+        // which output position it is destined for.
+        let mut destination = vec![0; perm.len()];
+        for (i, &from) in perm.iter().enumerate() {
+            destination[from] = i;
+        }
+        for i in 0..xs.len() {
+            while destination[i] != i {
+                let j = destination[i];
+                xs.swap(i, j);
+                destination.swap(i, j);
+            }
         }
-        permuted.reverse();
-        permuted
     }
 }
 
@@ -89,7 +177,10 @@ impl PermutationCounts {
 /// 8! fits in a u16
 /// 12! fits in a u32
 /// 20! fits in a u64
-pub fn encode_permutation<T: Ord, Encoded: TryFrom<usize>>(
+///
+/// Runs in O(n log n), using a Fenwick tree to count inversions instead of rescanning `data`.
+/// With the `bigint` feature enabled, `n!` no longer has to fit in a `usize`.
+pub fn encode_permutation<T: Ord, Encoded: TryFrom<Coord>>(
     data: &[T],
 ) -> Result<Encoded, Encoded::Error> {
     PermutationCounts::from_data(data).encode().try_into()
@@ -102,11 +193,99 @@ pub fn encode_permutation<T: Ord, Encoded: TryFrom<usize>>(
 ///
 /// Output a Vec with the data ordered in the unique permutation that matches this permutation
 /// number
-pub fn decode_permutation<'a, T: Ord + Clone, ToDecode: TryInto<usize>>(
+///
+/// Runs in O(n log n), using a Fenwick tree as an order-statistics structure over the
+/// still-available elements instead of rescanning them linearly for each position.
+///
+/// This allocates a fresh, sorted copy of `data` to permute. If `data` is already sorted and you
+/// want to avoid that allocation, use `decode_permutation_mut` instead.
+pub fn decode_permutation<'a, T: Ord + Clone, ToDecode: TryInto<Coord>>(
     permutation: ToDecode,
     data: &'a [T],
 ) -> Result<Vec<T>, ToDecode::Error> {
-    Ok(PermutationCounts::decode(permutation.try_into()?, data.len()).apply(&data))
+    let mut sorted = data.to_vec();
+    sorted.sort();
+    decode_permutation_mut(&mut sorted, permutation)?;
+    Ok(sorted)
+}
+
+/// Decode a permutation number into a unique permutation of `xs`, in place.
+///
+/// Precondition: `xs` must already be sorted in ascending order.
+///
+/// Unlike `decode_permutation`, this doesn't allocate a sorted copy of the data: it swaps each
+/// element of `xs` directly into its final position using the same Fenwick-tree order-statistics
+/// structure used to decode the permutation number itself, so it runs in O(n log n) with no
+/// allocation proportional to `T`.
+///
+/// When the elements of `xs` are distinct and `p` is below `xs.len()!`, `encode_permutation(xs)`
+/// called after `decode_permutation_mut(xs, p)` returns `p`. With duplicate elements, several
+/// permutation numbers decode to the same arrangement, so the re-encoded number can differ.
+pub fn decode_permutation_mut<T: Ord, ToDecode: TryInto<Coord>>(
+    xs: &mut [T],
+    permutation: ToDecode,
+) -> Result<(), ToDecode::Error> {
+    PermutationCounts::decode(permutation.try_into()?, xs.len()).apply_mut(xs);
+    Ok(())
+}
+
+/// Rearrange `data` into the next permutation in lexicographic order, in place.
+///
+/// Returns `true` if there was a next permutation. When `data` is already the last permutation
+/// (sorted in descending order), it is instead rearranged back into the first one (sorted in
+/// ascending order) and `false` is returned, so that repeated calls cycle through every
+/// permutation of `data`.
+pub fn next_permutation<T: Ord>(data: &mut [T]) -> bool {
+    if data.len() < 2 {
+        return false;
+    }
+    // Find the largest `i` such that `data[i] < data[i + 1]`.
+    let pivot = (0..data.len() - 1).rev().find(|&i| data[i] < data[i + 1]);
+    let pivot = match pivot {
+        Some(pivot) => pivot,
+        None => {
+            data.reverse();
+            return false;
+        }
+    };
+    // Find the largest `j > pivot` such that `data[j] > data[pivot]`, and swap them: `data[j]`
+    // is the smallest value to the right of `pivot` that's still bigger than it.
+    let successor = (pivot + 1..data.len())
+        .rev()
+        .find(|&j| data[j] > data[pivot])
+        .unwrap();
+    data.swap(pivot, successor);
+    data[pivot + 1..].reverse();
+    true
+}
+
+/// An iterator over every permutation of a slice, in lexicographic order.
+///
+/// Created by `all_permutations`.
+pub struct Permutations<T> {
+    next: Option<Vec<T>>,
+}
+
+impl<T: Ord + Clone> Iterator for Permutations<T> {
+    type Item = Vec<T>;
+    fn next(&mut self) -> Option<Vec<T>> {
+        let current = self.next.take()?;
+        let mut next = current.clone();
+        if next_permutation(&mut next) {
+            self.next = Some(next);
+        }
+        Some(current)
+    }
+}
+
+/// Enumerate every permutation of `data`, in lexicographic order, starting from the smallest one.
+///
+/// This is the counterpart to `encode_permutation`/`decode_permutation`: instead of decoding one
+/// permutation number at a time, it walks every arrangement directly using `next_permutation`.
+pub fn all_permutations<T: Ord + Clone>(data: &[T]) -> Permutations<T> {
+    let mut sorted = data.to_vec();
+    sorted.sort();
+    Permutations { next: Some(sorted) }
 }
 
 #[cfg(test)]
@@ -131,4 +310,69 @@ mod tests {
         shuffled.shuffle(&mut rng);
         assert_eq!(decode_permutation(21021, &shuffled), Ok(seq));
     }
+    #[test]
+    fn test_roundtrip_with_duplicates() {
+        let seq = [3u8, 1, 1, 2, 0, 2, 3, 1];
+        let encoded: usize = encode_permutation(&seq).unwrap();
+        let mut sorted = seq.to_vec();
+        sorted.sort();
+        assert_eq!(decode_permutation(encoded, &sorted), Ok(seq.to_vec()));
+    }
+    #[test]
+    fn test_decode_permutation_mut() {
+        let mut sorted = SEQ.to_vec();
+        sorted.sort();
+        decode_permutation_mut(&mut sorted, 21021usize).unwrap();
+        assert_eq!(sorted, SEQ.to_vec());
+    }
+    #[test]
+    fn test_decode_permutation_mut_then_encode_permutation_is_identity() {
+        let mut sorted = SEQ.to_vec();
+        sorted.sort();
+        decode_permutation_mut(&mut sorted, 21021usize).unwrap();
+        assert_eq!(Ok(21021), encode_permutation(&sorted));
+    }
+    #[test]
+    fn test_next_permutation() {
+        let mut data = vec![1, 2, 3];
+        assert!(next_permutation(&mut data));
+        assert_eq!(data, vec![1, 3, 2]);
+        assert!(next_permutation(&mut data));
+        assert_eq!(data, vec![2, 1, 3]);
+        assert!(next_permutation(&mut data));
+        assert_eq!(data, vec![2, 3, 1]);
+        assert!(next_permutation(&mut data));
+        assert_eq!(data, vec![3, 1, 2]);
+        assert!(next_permutation(&mut data));
+        assert_eq!(data, vec![3, 2, 1]);
+        assert!(!next_permutation(&mut data));
+        assert_eq!(data, vec![1, 2, 3]);
+    }
+    #[test]
+    fn test_all_permutations() {
+        let permutations = all_permutations(&[2, 1, 3]).collect::<Vec<_>>();
+        assert_eq!(
+            permutations,
+            vec![
+                vec![1, 2, 3],
+                vec![1, 3, 2],
+                vec![2, 1, 3],
+                vec![2, 3, 1],
+                vec![3, 1, 2],
+                vec![3, 2, 1],
+            ]
+        );
+    }
+    #[test]
+    fn test_all_permutations_is_strictly_increasing_and_exhaustive() {
+        use std::collections::BTreeSet;
+        let permutations = all_permutations(&SEQ).collect::<Vec<_>>();
+        let factorial: usize = (1..=SEQ.len()).product();
+        assert_eq!(permutations.len(), factorial);
+        assert_eq!(
+            permutations.iter().cloned().collect::<BTreeSet<_>>().len(),
+            factorial
+        );
+        assert!(permutations.windows(2).all(|pair| pair[0] < pair[1]));
+    }
 }
@@ -1,4 +1,4 @@
-use num_integer::binomial;
+use crate::coord::{self, Coord};
 use std::convert::{TryFrom, TryInto};
 
 /// Encode the position of the interesting elements in a slice as a single natural number.
@@ -7,7 +7,9 @@ use std::convert::{TryFrom, TryInto};
 ///
 /// In the context of a Rubiks Cube, the calculation is explained with an example here:
 /// http://kociemba.org/math/UDSliceCoord.htm
-pub fn encode_position<T: Ord, Encoded: TryFrom<usize>>(
+///
+/// With the `bigint` feature enabled, `C(len, num_interesting)` no longer has to fit in a `usize`.
+pub fn encode_position<T: Ord, Encoded: TryFrom<Coord>>(
     data: &[T],
     is_interesting: &dyn Fn(&T) -> bool,
 ) -> Result<Encoded, Encoded::Error> {
@@ -27,8 +29,8 @@ pub fn encode_position<T: Ord, Encoded: TryFrom<usize>>(
                 Some((index, interesting_to_the_left))
             }
         })
-        .map(|(index, interesting_to_the_left)| binomial(index, interesting_to_the_left - 1))
-        .sum::<usize>()
+        .map(|(index, interesting_to_the_left)| coord::binomial(index, interesting_to_the_left - 1))
+        .sum::<Coord>()
         .try_into()
 }
 
@@ -36,7 +38,7 @@ pub fn encode_position<T: Ord, Encoded: TryFrom<usize>>(
 /// Returns a Vec<bool> filled with false for all uninteresting elements and true for all
 /// interesting elements.
 /// This Vec can be used as a mapping of indices to interesting elements.
-pub fn decode_position<ToDecode: TryInto<usize>>(
+pub fn decode_position<ToDecode: TryInto<Coord>>(
     position: ToDecode,
     num_interesting: usize,
     len: usize,
@@ -47,9 +49,9 @@ pub fn decode_position<ToDecode: TryInto<usize>>(
         .rev()
         .map(|index| {
             let cutoff = if interesting_to_the_left > 0 {
-                binomial(index, interesting_to_the_left - 1).into()
+                coord::binomial(index, interesting_to_the_left - 1)
             } else {
-                0
+                coord::zero()
             };
             if position < cutoff {
                 interesting_to_the_left -= 1;
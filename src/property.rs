@@ -1,3 +1,4 @@
+use crate::coord::{self, Coord};
 use std::char::from_digit;
 use std::convert::{TryFrom, TryInto};
 use std::iter::{once, repeat};
@@ -9,7 +10,9 @@ use std::iter::{once, repeat};
 /// to say that summing all the values of the property should give a multiple of the
 /// base. This is used to omit one "bit" in that base as it can be reconstituted later using
 /// parity.
-pub fn encode_property<T: Ord, Encoded: TryFrom<usize>>(
+///
+/// With the `bigint` feature enabled, `base^(data.len() - 1)` no longer has to fit in a `usize`.
+pub fn encode_property<T: Ord, Encoded: TryFrom<Coord>>(
     data: &[T],
     property_mapping: &dyn Fn(&T) -> u8,
     base: u8,
@@ -25,9 +28,7 @@ pub fn encode_property<T: Ord, Encoded: TryFrom<usize>>(
         .map(|digit| from_digit(digit as u32, base as u32).unwrap())
         .collect::<String>();
 
-    usize::from_str_radix(&bits_string, base as u32)
-        .expect("The orientation1 should be convertible to the correct radix")
-        .try_into()
+    coord::from_radix_str(&bits_string, base).try_into()
 }
 
 /// Decode a property number into a unique ordering of this slice's property.
@@ -35,13 +36,13 @@ pub fn encode_property<T: Ord, Encoded: TryFrom<usize>>(
 /// Take as input the property number produced by `encode_property` and the base that was used to
 /// encode it.
 /// Returns a Vec<u8> filled with the value for the property at each position.
-pub fn decode_property<ToDecode: TryInto<usize>>(
+pub fn decode_property<ToDecode: TryInto<Coord>>(
     property: ToDecode,
     base: u8,
     len: usize,
 ) -> Result<Vec<u8>, ToDecode::Error> {
     let property = property.try_into()?;
-    let bits_string = format!("{}", radix_fmt::radix(property, base));
+    let bits_string = coord::to_radix_string(property, base);
     let last_digit = (base as u32
         - bits_string
             .chars()
@@ -0,0 +1,81 @@
+//! Uniformly random coordinates, for callers who want a random permutation or a random
+//! k-subset without shuffling or rejection sampling.
+//!
+//! Since every coordinate system in this crate is a perfect bijection onto `0..n!` or
+//! `0..C(len, num_interesting)`, sampling the integer uniformly and unranking it gives a
+//! uniformly random result directly.
+
+use crate::coord::{self, Coord};
+use crate::permutation::decode_permutation_mut;
+use crate::position::decode_position;
+use rand::Rng;
+
+/// Draw a permutation number uniformly at random from `0..n!`.
+pub fn random_permutation<R: Rng>(rng: &mut R, n: usize) -> Coord {
+    coord::random_below(rng, coord::factorial(n))
+}
+
+/// Draw a position number uniformly at random from `0..C(len, num_interesting)`.
+pub fn random_position<R: Rng>(rng: &mut R, num_interesting: usize, len: usize) -> Coord {
+    coord::random_below(rng, coord::binomial(len, num_interesting))
+}
+
+/// Rearrange `xs` into a uniformly random one of its `n!` orderings, in place.
+///
+/// A convenience wrapper around `random_permutation` and `decode_permutation_mut`.
+/// Precondition: `xs` must already be sorted in ascending order (see `decode_permutation_mut`).
+pub fn shuffle<T: Ord, R: Rng>(rng: &mut R, xs: &mut [T]) {
+    let permutation = random_permutation(rng, xs.len());
+    decode_permutation_mut(xs, permutation).unwrap();
+}
+
+/// Choose a uniformly random subset of `num_interesting` positions out of `len`.
+///
+/// A convenience wrapper around `random_position` and `decode_position`: returns a `Vec<bool>`
+/// mask, `true` for each chosen position.
+pub fn random_subset<R: Rng>(rng: &mut R, num_interesting: usize, len: usize) -> Vec<bool> {
+    let position = random_position(rng, num_interesting, len);
+    decode_position(position, num_interesting, len).unwrap()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::thread_rng;
+
+    #[test]
+    fn test_random_permutation_is_in_range() {
+        let mut rng = thread_rng();
+        for _ in 0..100 {
+            assert!(random_permutation(&mut rng, 8) < coord::factorial(8));
+        }
+    }
+
+    #[test]
+    fn test_random_position_is_in_range() {
+        let mut rng = thread_rng();
+        for _ in 0..100 {
+            assert!(random_position(&mut rng, 3, 12) < coord::binomial(12, 3));
+        }
+    }
+
+    #[test]
+    fn test_shuffle_is_a_permutation_of_the_input() {
+        let mut rng = thread_rng();
+        let original = vec![0u8, 1, 2, 3, 4, 5, 6, 7];
+        let mut xs = original.clone();
+        xs.sort();
+        shuffle(&mut rng, &mut xs);
+        let mut sorted_again = xs.clone();
+        sorted_again.sort();
+        assert_eq!(sorted_again, original);
+    }
+
+    #[test]
+    fn test_random_subset_has_the_right_cardinality() {
+        let mut rng = thread_rng();
+        let mask = random_subset(&mut rng, 3, 12);
+        assert_eq!(mask.len(), 12);
+        assert_eq!(mask.iter().filter(|x| **x).count(), 3);
+    }
+}